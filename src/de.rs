@@ -1,7 +1,9 @@
 use pest::iterators::{Pair, Pairs};
 use pest::Parser;
+use serde::de::value::StringDeserializer;
 use serde::de::{
-    Deserialize, DeserializeSeed, Deserializer, SeqAccess, Visitor,
+    Deserialize, DeserializeSeed, Deserializer, Error as DeError, IntoDeserializer, SeqAccess,
+    Unexpected, Visitor,
 };
 use std::char;
 use std::f64::{INFINITY, NAN, NEG_INFINITY};
@@ -45,23 +47,104 @@ impl<'de, 'a> Deserializer<'de> for &'a mut Json5Deserializer<'de> {
         V: Visitor<'de>,
     {
         let pair = self.pair.take().unwrap();
-        match pair.as_rule() {
+        let span = pair.as_span();
+        let result = match pair.as_rule() {
             Rule::null => visitor.visit_unit(),
             Rule::boolean => visitor.visit_bool(parse_bool(pair)),
             Rule::string => visitor.visit_string(parse_string(pair)),
-            Rule::number => visitor.visit_f64(parse_number(pair)),
+            Rule::number => match parse_number(pair) {
+                Ok(Number::Float(n)) => visitor.visit_f64(n),
+                Ok(Number::PosInt(n)) => visitor.visit_u64(n),
+                Ok(Number::NegInt(n)) => visitor.visit_i64(n),
+                Ok(Number::PosBigInt(n)) => visitor.visit_u128(n),
+                Ok(Number::NegBigInt(n)) => visitor.visit_i128(n),
+                Err(e) => Err(e),
+            },
             Rule::array => visitor.visit_seq(Access::to(pair.into_inner())),
-            // TODO
-            // Rule::object => visitor.visit_map(Access::to(pair.into_inner())),
+            Rule::object => visitor.visit_map(MapAccess::to(pair.into_inner())),
             _ => unreachable!(),
+        };
+        result.map_err(|e| e.with_location(span))
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if self.pair.as_ref().unwrap().as_rule() == Rule::null {
+            self.pair.take();
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
         }
     }
 
-    // TODO Probably don't want to forward enum, struct, etc...
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let pair = self.pair.take().unwrap();
+        let span = pair.as_span();
+        let result = match pair.as_rule() {
+            Rule::string => visitor.visit_enum(EnumAccess {
+                variant: pair,
+                value: None,
+            }),
+            Rule::object => {
+                let mut inner = pair.into_inner();
+                match inner.next() {
+                    Some(variant) => {
+                        let value = inner.next();
+                        visitor.visit_enum(EnumAccess { variant, value })
+                    }
+                    None => Err(DeError::invalid_value(
+                        Unexpected::Map,
+                        &"an object with exactly one key naming the enum variant",
+                    )),
+                }
+            }
+            _ => Err(DeError::invalid_type(
+                unexpected(&pair),
+                &"a string or an object naming the enum variant",
+            )),
+        };
+        result.map_err(|e| e.with_location(span))
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
     forward_to_deserialize_any! {
         bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
-        bytes byte_buf option unit unit_struct newtype_struct seq tuple
-        tuple_struct map struct enum identifier ignored_any
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map identifier
     }
 }
 
@@ -73,6 +156,20 @@ fn parse_bool(pair: Pair<Rule>) -> bool {
     }
 }
 
+// Describes a pair that turned out not to be the shape the caller wanted,
+// for use in an `invalid_type` error. `Rule::string` and `Rule::object` are
+// handled by their callers directly, since building their `Unexpected`
+// requires actually parsing the pair.
+fn unexpected(pair: &Pair<Rule>) -> Unexpected {
+    match pair.as_rule() {
+        Rule::null => Unexpected::Unit,
+        Rule::boolean => Unexpected::Bool(pair.as_str() == "true"),
+        Rule::array => Unexpected::Seq,
+        Rule::number => Unexpected::Other("number"),
+        _ => Unexpected::Other("value"),
+    }
+}
+
 fn parse_string(pair: Pair<Rule>) -> String {
     pair.into_inner()
         .map(|component| match component.as_rule() {
@@ -101,13 +198,70 @@ fn parse_char_escape_sequence(pair: Pair<Rule>) -> String {
     })
 }
 
-fn parse_number(pair: Pair<Rule>) -> f64 {
-    match pair.as_str() {
-        "Infinity" => INFINITY,
-        "-Infinity" => NEG_INFINITY,
-        "NaN" | "-NaN" => NAN,
-        s if is_hex_literal(s) => parse_hex(&s[2..]) as f64,
-        s => s.parse().unwrap(),
+enum Number {
+    Float(f64),
+    PosInt(u64),
+    NegInt(i64),
+    PosBigInt(u128),
+    NegBigInt(i128),
+}
+
+// Classifies the literal so integers can be handed to the visitor without
+// first losing precision by going through f64. `5.`, `.5` and `-0` are
+// treated as floats, matching their JSON5 intent; a leading `+` is only a
+// float unless it prefixes a hex literal (`+0x1` is valid JSON5 hex).
+fn parse_number(pair: Pair<Rule>) -> Result<Number> {
+    let s = pair.as_str();
+    if s == "Infinity" {
+        return Ok(Number::Float(INFINITY));
+    }
+    if s == "-Infinity" {
+        return Ok(Number::Float(NEG_INFINITY));
+    }
+    if s == "NaN" || s == "-NaN" {
+        return Ok(Number::Float(NAN));
+    }
+    if s.starts_with("-0x") || s.starts_with("-0X") {
+        return Err(DeError::custom(
+            "negative hexadecimal literals are not valid JSON5 numbers",
+        ));
+    }
+    let hex_digits = if is_hex_literal(s) {
+        Some(&s[2..])
+    } else if s.starts_with("+0x") || s.starts_with("+0X") {
+        Some(&s[3..])
+    } else {
+        None
+    };
+    if let Some(digits) = hex_digits {
+        return match u64::from_str_radix(digits, 16) {
+            Ok(n) => Ok(Number::PosInt(n)),
+            Err(_) => match u128::from_str_radix(digits, 16) {
+                Ok(n) => Ok(Number::PosBigInt(n)),
+                Err(_) => Err(DeError::custom(
+                    "hexadecimal literal is too large to represent",
+                )),
+            },
+        };
+    }
+    if s == "-0" || s.starts_with('+') || s.contains('.') || s.contains('e') || s.contains('E') {
+        return Ok(Number::Float(s.parse().unwrap()));
+    }
+    if s.starts_with('-') {
+        return match s.parse::<i64>() {
+            Ok(n) => Ok(Number::NegInt(n)),
+            Err(_) => match s.parse::<i128>() {
+                Ok(n) => Ok(Number::NegBigInt(n)),
+                Err(_) => Err(DeError::custom("integer literal is too large to represent")),
+            },
+        };
+    }
+    match s.parse::<u64>() {
+        Ok(n) => Ok(Number::PosInt(n)),
+        Err(_) => match s.parse::<u128>() {
+            Ok(n) => Ok(Number::PosBigInt(n)),
+            Err(_) => Err(DeError::custom("integer literal is too large to represent")),
+        },
     }
 }
 
@@ -144,3 +298,196 @@ impl<'de> SeqAccess<'de> for Access<'de> {
         }
     }
 }
+
+struct MapAccess<'de> {
+    pairs: Pairs<'de, Rule>,
+}
+
+impl<'de> MapAccess<'de> {
+    fn to(pairs: Pairs<'de, Rule>) -> Self {
+        MapAccess { pairs }
+    }
+}
+
+impl<'de> serde::de::MapAccess<'de> for MapAccess<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        if let Some(pair) = self.pairs.next() {
+            let key = parse_key(pair);
+            let deserializer: StringDeserializer<Error> = key.into_deserializer();
+            seed.deserialize(deserializer).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let pair = self.pairs.next().unwrap();
+        seed.deserialize(&mut Json5Deserializer::from_pair(pair))
+    }
+}
+
+// Object keys may be bare identifiers, or single- or double-quoted strings;
+// in every case the key is deserialized as a plain string.
+fn parse_key(pair: Pair<Rule>) -> String {
+    match pair.as_rule() {
+        Rule::string => parse_string(pair),
+        _ => String::from(pair.as_str()),
+    }
+}
+
+struct EnumAccess<'de> {
+    variant: Pair<'de, Rule>,
+    value: Option<Pair<'de, Rule>>,
+}
+
+impl<'de> serde::de::EnumAccess<'de> for EnumAccess<'de> {
+    type Error = Error;
+    type Variant = VariantAccess<'de>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant)>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let key = parse_key(self.variant);
+        let deserializer: StringDeserializer<Error> = key.into_deserializer();
+        let value = seed.deserialize(deserializer)?;
+        Ok((value, VariantAccess { value: self.value }))
+    }
+}
+
+struct VariantAccess<'de> {
+    value: Option<Pair<'de, Rule>>,
+}
+
+impl<'de> serde::de::VariantAccess<'de> for VariantAccess<'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.value {
+            Some(pair) => seed.deserialize(&mut Json5Deserializer::from_pair(pair)),
+            None => Err(DeError::invalid_type(Unexpected::UnitVariant, &"newtype variant")),
+        }
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Some(pair) => visitor.visit_seq(Access::to(pair.into_inner())),
+            None => Err(DeError::invalid_type(Unexpected::UnitVariant, &"tuple variant")),
+        }
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Some(pair) => visitor.visit_map(MapAccess::to(pair.into_inner())),
+            None => Err(DeError::invalid_type(Unexpected::UnitVariant, &"struct variant")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_str, Result};
+    use std::collections::HashMap;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    #[test]
+    fn deserializes_an_object_into_a_struct() {
+        let point: Point = from_str("{x: 1, y: 2}").unwrap();
+        assert_eq!(point, Point { x: 1, y: 2 });
+    }
+
+    #[test]
+    fn deserializes_an_object_into_a_map() {
+        let map: HashMap<String, i32> = from_str("{a: 1, b: 2}").unwrap();
+        assert_eq!(map.get("a"), Some(&1));
+        assert_eq!(map.get("b"), Some(&2));
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    enum Shape {
+        Circle(f64),
+        Named { label: String },
+    }
+
+    #[test]
+    fn deserializes_newtype_and_struct_enum_variants() {
+        let shapes: Vec<Shape> = from_str("[{Circle: 1.5}, {Named: {label: 'square'}}]").unwrap();
+        assert_eq!(
+            shapes,
+            vec![
+                Shape::Circle(1.5),
+                Shape::Named {
+                    label: "square".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn errors_instead_of_panicking_on_a_unit_variant_expecting_a_payload() {
+        let result: Result<Shape> = from_str("\"Circle\"");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn hex_literal_wider_than_u64_falls_back_to_u128() {
+        let huge: u128 = from_str("0xffffffffffffffffff").unwrap();
+        assert_eq!(huge, u128::from_str_radix("ffffffffffffffffff", 16).unwrap());
+    }
+
+    #[test]
+    fn sign_prefixed_hex_literal_is_classified_as_hex_not_float() {
+        let n: u32 = from_str("+0x1").unwrap();
+        assert_eq!(n, 1);
+    }
+
+    #[test]
+    fn error_location_points_at_the_deepest_mismatched_value() {
+        let result: Result<Point> = from_str("{x: 'foo', y: 2}");
+        let err = result.unwrap_err();
+        // The field value `'foo'` starts at byte 4 (0-indexed), line 1,
+        // column 5 (1-indexed) — not at the enclosing `{` the struct as a
+        // whole was parsed from, confirming the innermost span wins.
+        assert_eq!(err.location(), Some((1, 5)));
+        assert_eq!(err.byte_offset(), Some(4));
+    }
+
+    #[test]
+    fn errors_instead_of_panicking_on_integer_literals_too_large_for_128_bits() {
+        let too_big = format!("1{}", "0".repeat(40));
+        let result: Result<u128> = from_str(&too_big);
+        assert!(result.is_err());
+
+        let result: Result<i128> = from_str(&format!("-{}", too_big));
+        assert!(result.is_err());
+
+        let result: Result<u128> = from_str(&format!("0x{}", "f".repeat(40)));
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,130 @@
+use pest::error::{Error as PestError, InputLocation, LineColLocation};
+use pest::Span;
+use serde::{de, ser};
+use std::error;
+use std::fmt;
+use std::io;
+use std::result;
+
+use de::Rule;
+
+/// The result type returned by this crate's parsing and (de)serialization
+/// entry points.
+pub type Result<T> = result::Result<T, Error>;
+
+/// The position within the source document an [`Error`] occurred at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+    pub byte_offset: usize,
+}
+
+/// An error encountered while parsing or (de)serializing a JSON5 document.
+#[derive(Debug)]
+pub struct Error {
+    message: String,
+    location: Option<Location>,
+}
+
+impl Error {
+    // Attaches `span` as the error's location if it doesn't already have
+    // one; the deepest call site (the one closest to the actual bad token)
+    // sets it first, so outer wrapping never clobbers a more precise spot.
+    pub(crate) fn with_location(mut self, span: Span) -> Error {
+        if self.location.is_none() {
+            let (line, column) = span.start_pos().line_col();
+            self.location = Some(Location {
+                line,
+                column,
+                byte_offset: span.start(),
+            });
+        }
+        self
+    }
+
+    /// The `(line, column)` the error occurred at, if known.
+    ///
+    /// Both are 1-indexed, matching pest's own convention.
+    pub fn location(&self) -> Option<(usize, usize)> {
+        self.location.map(|loc| (loc.line, loc.column))
+    }
+
+    /// The byte offset into the source document the error occurred at, if
+    /// known.
+    pub fn byte_offset(&self) -> Option<usize> {
+        self.location.map(|loc| loc.byte_offset)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.location {
+            Some(loc) => write!(
+                f,
+                "{} at line {} column {}",
+                self.message, loc.line, loc.column
+            ),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl de::Error for Error {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        Error {
+            message: msg.to_string(),
+            location: None,
+        }
+    }
+}
+
+impl ser::Error for Error {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        Error {
+            message: msg.to_string(),
+            location: None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        Error {
+            message: err.to_string(),
+            location: None,
+        }
+    }
+}
+
+impl From<PestError<Rule>> for Error {
+    fn from(err: PestError<Rule>) -> Error {
+        let (line, column) = match err.line_col {
+            LineColLocation::Pos(pos) => pos,
+            LineColLocation::Span(start, _) => start,
+        };
+        let byte_offset = match err.location {
+            InputLocation::Pos(pos) => pos,
+            InputLocation::Span((start, _)) => start,
+        };
+        Error {
+            // `err.to_string()` renders pest's own "line:col" position into
+            // the message; use just the variant's description here so
+            // Display doesn't end up printing the position twice.
+            message: err.variant.message().into_owned(),
+            location: Some(Location {
+                line,
+                column,
+                byte_offset,
+            }),
+        }
+    }
+}
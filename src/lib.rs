@@ -0,0 +1,22 @@
+//! A Rust JSON5 serializer and deserializer which speaks Serde.
+
+extern crate linked_hash_map;
+extern crate pest;
+#[macro_use]
+extern crate pest_derive;
+#[macro_use]
+extern crate serde;
+#[cfg(test)]
+#[macro_use]
+extern crate serde_derive;
+
+mod de;
+mod error;
+mod ser;
+mod value;
+
+pub use de::{from_str, Json5Deserializer};
+pub use error::{Error, Location, Result};
+pub use ser::{to_string, to_string_with_config, to_writer, to_writer_with_config};
+pub use ser::{Config, Quote, Serializer};
+pub use value::{Map, Value};
@@ -0,0 +1,832 @@
+use serde::ser::{self, Serialize};
+use std::io;
+
+use error::{Error, Result};
+
+/// Which quote character to prefer when writing out strings and identifier
+/// keys that need quoting.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Quote {
+    Double,
+    Single,
+}
+
+/// Knobs controlling the JSON5-specific sugar a [`Serializer`] emits.
+///
+/// The defaults produce compact, strict-JSON-compatible output, except that
+/// non-finite floats are written as the bare `Infinity`/`-Infinity`/`NaN`
+/// tokens; set `indent` to pretty-print, or loosen `quote`/`unquoted_keys`/
+/// `trailing_commas` to take advantage of what JSON5 allows on the way back
+/// in.
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Quote character used for strings and for object keys that require
+    /// quoting.
+    pub quote: Quote,
+    /// Write bare identifier keys (e.g. `foo: 1`) when the key is a valid
+    /// ECMAScript identifier, instead of always quoting.
+    pub unquoted_keys: bool,
+    /// Emit a trailing comma after the last element of an array or object.
+    /// The `{ variant: ... }` object wrapping an enum variant's payload is
+    /// written without one.
+    pub trailing_commas: bool,
+    /// Number of spaces to indent by when pretty-printing. `None` emits
+    /// everything on one line.
+    pub indent: Option<usize>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            quote: Quote::Double,
+            unquoted_keys: false,
+            trailing_commas: false,
+            indent: None,
+        }
+    }
+}
+
+/// Serializes `value` as a JSON5 string using the default [`Config`].
+pub fn to_string<T>(value: &T) -> Result<String>
+where
+    T: Serialize,
+{
+    to_string_with_config(value, Config::default())
+}
+
+/// Serializes `value` as a JSON5 string using the given [`Config`].
+pub fn to_string_with_config<T>(value: &T, config: Config) -> Result<String>
+where
+    T: Serialize,
+{
+    let mut buf = Vec::new();
+    to_writer_with_config(&mut buf, value, config)?;
+    Ok(String::from_utf8(buf).expect("JSON5 serializer only ever writes valid UTF-8"))
+}
+
+/// Serializes `value` as JSON5 into `writer` using the default [`Config`].
+pub fn to_writer<W, T>(writer: W, value: &T) -> Result<()>
+where
+    W: io::Write,
+    T: Serialize,
+{
+    to_writer_with_config(writer, value, Config::default())
+}
+
+/// Serializes `value` as JSON5 into `writer` using the given [`Config`].
+pub fn to_writer_with_config<W, T>(writer: W, value: &T, config: Config) -> Result<()>
+where
+    W: io::Write,
+    T: Serialize,
+{
+    let mut ser = Serializer::with_config(writer, config);
+    value.serialize(&mut ser)
+}
+
+/// A structure for serializing Rust values into JSON5.
+pub struct Serializer<W> {
+    writer: W,
+    config: Config,
+    indent_level: usize,
+}
+
+impl<W> Serializer<W>
+where
+    W: io::Write,
+{
+    pub fn new(writer: W) -> Self {
+        Serializer::with_config(writer, Config::default())
+    }
+
+    pub fn with_config(writer: W, config: Config) -> Self {
+        Serializer {
+            writer,
+            config,
+            indent_level: 0,
+        }
+    }
+
+    fn write_newline_and_indent(&mut self) -> Result<()> {
+        if let Some(width) = self.config.indent {
+            self.writer.write_all(b"\n")?;
+            for _ in 0..self.indent_level * width {
+                self.writer.write_all(b" ")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_quoted(&mut self, s: &str) -> Result<()> {
+        let quote = match self.config.quote {
+            Quote::Double => b'"',
+            Quote::Single => b'\'',
+        };
+        self.writer.write_all(&[quote])?;
+        for c in s.chars() {
+            match c {
+                '\\' => self.writer.write_all(b"\\\\")?,
+                '\n' => self.writer.write_all(b"\\n")?,
+                '\r' => self.writer.write_all(b"\\r")?,
+                '\t' => self.writer.write_all(b"\\t")?,
+                c if (c as u32) < 0x20 => write!(self.writer, "\\u{:04x}", c as u32)?,
+                c if c as u32 == quote as u32 => {
+                    self.writer.write_all(&[b'\\', quote])?;
+                }
+                c => write!(self.writer, "{}", c)?,
+            }
+        }
+        self.writer.write_all(&[quote])?;
+        Ok(())
+    }
+
+    fn write_key(&mut self, key: &str) -> Result<()> {
+        if self.config.unquoted_keys && is_identifier(key) {
+            self.writer.write_all(key.as_bytes())?;
+            Ok(())
+        } else {
+            self.write_quoted(key)
+        }
+    }
+
+    fn write_float<F>(&mut self, v: F) -> Result<()>
+    where
+        F: Into<f64>,
+    {
+        let v = v.into();
+        if v.is_nan() {
+            self.writer.write_all(b"NaN")?;
+        } else if v.is_infinite() {
+            self.writer
+                .write_all(if v > 0.0 { b"Infinity" } else { b"-Infinity" })?;
+        } else {
+            let mut s = v.to_string();
+            if !s.contains('.') && !s.contains('e') && !s.contains('E') {
+                s.push_str(".0");
+            }
+            self.writer.write_all(s.as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+/// A valid ECMAScript `IdentifierName`, i.e. one that doesn't require
+/// quoting as a JSON5 object key: `$`/`_`/alphabetic first character,
+/// followed by any number of `$`/`_`/alphanumeric characters.
+fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) if c == '$' || c == '_' || c.is_alphabetic() => {}
+        _ => return false,
+    }
+    chars.all(|c| c == '$' || c == '_' || c.is_alphanumeric())
+}
+
+impl<'a, W> ser::Serializer for &'a mut Serializer<W>
+where
+    W: io::Write,
+{
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = Compound<'a, W>;
+    type SerializeTuple = Compound<'a, W>;
+    type SerializeTupleStruct = Compound<'a, W>;
+    type SerializeTupleVariant = Compound<'a, W>;
+    type SerializeMap = Compound<'a, W>;
+    type SerializeStruct = Compound<'a, W>;
+    type SerializeStructVariant = Compound<'a, W>;
+
+    fn serialize_bool(self, v: bool) -> Result<()> {
+        self.writer
+            .write_all(if v { b"true" } else { b"false" })?;
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<()> {
+        self.serialize_i64(v as i64)
+    }
+    fn serialize_i16(self, v: i16) -> Result<()> {
+        self.serialize_i64(v as i64)
+    }
+    fn serialize_i32(self, v: i32) -> Result<()> {
+        self.serialize_i64(v as i64)
+    }
+    fn serialize_i64(self, v: i64) -> Result<()> {
+        write!(self.writer, "{}", v)?;
+        Ok(())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<()> {
+        self.serialize_u64(v as u64)
+    }
+    fn serialize_u16(self, v: u16) -> Result<()> {
+        self.serialize_u64(v as u64)
+    }
+    fn serialize_u32(self, v: u32) -> Result<()> {
+        self.serialize_u64(v as u64)
+    }
+    fn serialize_u64(self, v: u64) -> Result<()> {
+        write!(self.writer, "{}", v)?;
+        Ok(())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<()> {
+        self.write_float(v)
+    }
+    fn serialize_f64(self, v: f64) -> Result<()> {
+        self.write_float(v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<()> {
+        self.write_quoted(&v.to_string())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<()> {
+        self.write_quoted(v)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<()> {
+        let mut seq = self.serialize_seq(Some(v.len()))?;
+        for byte in v {
+            ser::SerializeSeq::serialize_element(&mut seq, byte)?;
+        }
+        ser::SerializeSeq::end(seq)
+    }
+
+    fn serialize_none(self) -> Result<()> {
+        self.writer.write_all(b"null")?;
+        Ok(())
+    }
+
+    fn serialize_some<T: ?Sized>(self, value: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<()> {
+        self.writer.write_all(b"null")?;
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<()> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<()> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized>(self, _name: &'static str, value: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<()>
+    where
+        T: Serialize,
+    {
+        self.writer.write_all(b"{")?;
+        self.indent_level += 1;
+        self.write_newline_and_indent()?;
+        self.write_key(variant)?;
+        self.writer.write_all(b":")?;
+        if self.config.indent.is_some() {
+            self.writer.write_all(b" ")?;
+        }
+        value.serialize(&mut *self)?;
+        self.indent_level -= 1;
+        self.write_newline_and_indent()?;
+        self.writer.write_all(b"}")?;
+        Ok(())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        self.writer.write_all(b"[")?;
+        self.indent_level += 1;
+        Ok(Compound {
+            ser: self,
+            first: true,
+            end: "]",
+            suffix: "",
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        self.writer.write_all(b"{")?;
+        self.indent_level += 1;
+        self.write_newline_and_indent()?;
+        self.write_key(variant)?;
+        self.writer.write_all(b":")?;
+        if self.config.indent.is_some() {
+            self.writer.write_all(b" ")?;
+        }
+        self.writer.write_all(b"[")?;
+        self.indent_level += 1;
+        Ok(Compound {
+            ser: self,
+            first: true,
+            end: "]",
+            suffix: "}",
+        })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        self.writer.write_all(b"{")?;
+        self.indent_level += 1;
+        Ok(Compound {
+            ser: self,
+            first: true,
+            end: "}",
+            suffix: "",
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        self.writer.write_all(b"{")?;
+        self.indent_level += 1;
+        self.write_newline_and_indent()?;
+        self.write_key(variant)?;
+        self.writer.write_all(b":")?;
+        if self.config.indent.is_some() {
+            self.writer.write_all(b" ")?;
+        }
+        self.writer.write_all(b"{")?;
+        self.indent_level += 1;
+        Ok(Compound {
+            ser: self,
+            first: true,
+            end: "}",
+            suffix: "}",
+        })
+    }
+}
+
+/// Shared `SerializeSeq`/`SerializeMap`/`SerializeStruct` implementation:
+/// arrays and objects only differ in their delimiters and in whether
+/// entries are written as `value` or `key: value`.
+pub struct Compound<'a, W: 'a> {
+    ser: &'a mut Serializer<W>,
+    first: bool,
+    end: &'static str,
+    // Closing brace for the enclosing object when serializing a tuple- or
+    // struct-variant, which wraps the payload as `{ variant: <payload> }`.
+    suffix: &'static str,
+}
+
+impl<'a, W> Compound<'a, W>
+where
+    W: io::Write,
+{
+    fn separator(&mut self) -> Result<()> {
+        if !self.first {
+            self.ser.writer.write_all(b",")?;
+        }
+        self.first = false;
+        self.ser.write_newline_and_indent()
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.ser.indent_level -= 1;
+        if !self.first {
+            if self.ser.config.trailing_commas {
+                self.ser.writer.write_all(b",")?;
+            }
+            self.ser.write_newline_and_indent()?;
+        }
+        self.ser.writer.write_all(self.end.as_bytes())?;
+        if !self.suffix.is_empty() {
+            // Undo the extra `indent_level` bump serialize_tuple_variant/
+            // serialize_struct_variant added for the wrapping `{ variant: `
+            // object, so indentation doesn't keep drifting deeper with
+            // every variant serialized through this Serializer.
+            self.ser.indent_level -= 1;
+            self.ser.write_newline_and_indent()?;
+        }
+        self.ser.writer.write_all(self.suffix.as_bytes())?;
+        Ok(())
+    }
+}
+
+impl<'a, W> ser::SerializeSeq for Compound<'a, W>
+where
+    W: io::Write,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        self.separator()?;
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(mut self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<'a, W> ser::SerializeTuple for Compound<'a, W>
+where
+    W: io::Write,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<()> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl<'a, W> ser::SerializeTupleStruct for Compound<'a, W>
+where
+    W: io::Write,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<()> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl<'a, W> ser::SerializeTupleVariant for Compound<'a, W>
+where
+    W: io::Write,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<()> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl<'a, W> ser::SerializeMap for Compound<'a, W>
+where
+    W: io::Write,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: ?Sized>(&mut self, key: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        self.separator()?;
+        let key = key.serialize(MapKeySerializer)?;
+        self.ser.write_key(&key)
+    }
+
+    fn serialize_value<T: ?Sized>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        self.ser.writer.write_all(b":")?;
+        if self.ser.config.indent.is_some() {
+            self.ser.writer.write_all(b" ")?;
+        }
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(mut self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<'a, W> ser::SerializeStruct for Compound<'a, W>
+where
+    W: io::Write,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        self.separator()?;
+        self.ser.write_key(key)?;
+        self.ser.writer.write_all(b":")?;
+        if self.ser.config.indent.is_some() {
+            self.ser.writer.write_all(b" ")?;
+        }
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(mut self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<'a, W> ser::SerializeStructVariant for Compound<'a, W>
+where
+    W: io::Write,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        ser::SerializeStruct::serialize_field(self, key, value)
+    }
+
+    fn end(self) -> Result<()> {
+        ser::SerializeStruct::end(self)
+    }
+}
+
+/// Object keys must ultimately be strings; this mirrors serde_json's
+/// `MapKeySerializer` by only accepting the string-like serialize calls and
+/// erroring on anything else (numbers, sequences, etc.).
+struct MapKeySerializer;
+
+impl ser::Serializer for MapKeySerializer {
+    type Ok = String;
+    type Error = Error;
+
+    type SerializeSeq = ser::Impossible<String, Error>;
+    type SerializeTuple = ser::Impossible<String, Error>;
+    type SerializeTupleStruct = ser::Impossible<String, Error>;
+    type SerializeTupleVariant = ser::Impossible<String, Error>;
+    type SerializeMap = ser::Impossible<String, Error>;
+    type SerializeStruct = ser::Impossible<String, Error>;
+    type SerializeStructVariant = ser::Impossible<String, Error>;
+
+    fn serialize_str(self, v: &str) -> Result<String> {
+        Ok(String::from(v))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<String> {
+        Ok(String::from(variant))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized>(self, _name: &'static str, value: &T) -> Result<String>
+    where
+        T: Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_bool(self, _v: bool) -> Result<String> {
+        Err(ser::Error::custom("map key must be a string"))
+    }
+    fn serialize_i8(self, _v: i8) -> Result<String> {
+        Err(ser::Error::custom("map key must be a string"))
+    }
+    fn serialize_i16(self, _v: i16) -> Result<String> {
+        Err(ser::Error::custom("map key must be a string"))
+    }
+    fn serialize_i32(self, _v: i32) -> Result<String> {
+        Err(ser::Error::custom("map key must be a string"))
+    }
+    fn serialize_i64(self, _v: i64) -> Result<String> {
+        Err(ser::Error::custom("map key must be a string"))
+    }
+    fn serialize_u8(self, _v: u8) -> Result<String> {
+        Err(ser::Error::custom("map key must be a string"))
+    }
+    fn serialize_u16(self, _v: u16) -> Result<String> {
+        Err(ser::Error::custom("map key must be a string"))
+    }
+    fn serialize_u32(self, _v: u32) -> Result<String> {
+        Err(ser::Error::custom("map key must be a string"))
+    }
+    fn serialize_u64(self, _v: u64) -> Result<String> {
+        Err(ser::Error::custom("map key must be a string"))
+    }
+    fn serialize_f32(self, _v: f32) -> Result<String> {
+        Err(ser::Error::custom("map key must be a string"))
+    }
+    fn serialize_f64(self, _v: f64) -> Result<String> {
+        Err(ser::Error::custom("map key must be a string"))
+    }
+    fn serialize_char(self, v: char) -> Result<String> {
+        Ok(v.to_string())
+    }
+    fn serialize_bytes(self, _v: &[u8]) -> Result<String> {
+        Err(ser::Error::custom("map key must be a string"))
+    }
+    fn serialize_none(self) -> Result<String> {
+        Err(ser::Error::custom("map key must be a string"))
+    }
+    fn serialize_some<T: ?Sized>(self, value: &T) -> Result<String>
+    where
+        T: Serialize,
+    {
+        value.serialize(self)
+    }
+    fn serialize_unit(self) -> Result<String> {
+        Err(ser::Error::custom("map key must be a string"))
+    }
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String> {
+        Err(ser::Error::custom("map key must be a string"))
+    }
+    fn serialize_newtype_variant<T: ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String>
+    where
+        T: Serialize,
+    {
+        Err(ser::Error::custom("map key must be a string"))
+    }
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Err(ser::Error::custom("map key must be a string"))
+    }
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        Err(ser::Error::custom("map key must be a string"))
+    }
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Err(ser::Error::custom("map key must be a string"))
+    }
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(ser::Error::custom("map key must be a string"))
+    }
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Err(ser::Error::custom("map key must be a string"))
+    }
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct> {
+        Err(ser::Error::custom("map key must be a string"))
+    }
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(ser::Error::custom("map key must be a string"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::ser::{SerializeStruct, SerializeStructVariant};
+    use std::result;
+
+    struct Point {
+        x: i32,
+    }
+
+    impl Serialize for Point {
+        fn serialize<S>(&self, serializer: S) -> result::Result<S::Ok, S::Error>
+        where
+            S: ser::Serializer,
+        {
+            let mut s = serializer.serialize_struct("Point", 1)?;
+            s.serialize_field("x", &self.x)?;
+            s.end()
+        }
+    }
+
+    enum Shape {
+        Circle { radius: f64 },
+    }
+
+    impl Serialize for Shape {
+        fn serialize<S>(&self, serializer: S) -> result::Result<S::Ok, S::Error>
+        where
+            S: ser::Serializer,
+        {
+            match *self {
+                Shape::Circle { radius } => {
+                    let mut sv = serializer.serialize_struct_variant("Shape", 0, "Circle", 1)?;
+                    sv.serialize_field("radius", &radius)?;
+                    sv.end()
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn default_config_quotes_keys() {
+        assert_eq!(to_string(&Point { x: 1 }).unwrap(), "{\"x\":1}");
+    }
+
+    #[test]
+    fn unquoted_keys_emit_bare_identifiers() {
+        let config = Config {
+            unquoted_keys: true,
+            ..Config::default()
+        };
+        assert_eq!(
+            to_string_with_config(&Point { x: 1 }, config).unwrap(),
+            "{x:1}"
+        );
+    }
+
+    #[test]
+    fn pretty_printing_struct_variants_does_not_leak_indentation() {
+        let shapes = vec![
+            Shape::Circle { radius: 1.0 },
+            Shape::Circle { radius: 2.0 },
+        ];
+        let config = Config {
+            indent: Some(2),
+            ..Config::default()
+        };
+        let output = to_string_with_config(&shapes, config).unwrap();
+
+        // Before the indent-level fix, each subsequent struct variant
+        // serialized through the same `Serializer` ended up one level
+        // deeper than the last.
+        let indents: Vec<usize> = output
+            .lines()
+            .filter(|line| line.trim_start().starts_with("\"radius\""))
+            .map(|line| line.len() - line.trim_start().len())
+            .collect();
+        assert_eq!(indents.len(), 2);
+        assert_eq!(indents[0], indents[1]);
+    }
+}
@@ -0,0 +1,214 @@
+use linked_hash_map::LinkedHashMap;
+use serde::de::{Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::Index;
+
+/// An order-preserving map from `String` keys to [`Value`]s, used to back
+/// `Value::Object`. JSON5 (like JSON) does not mandate any particular
+/// ordering, but preserving the order the keys were written in is the least
+/// surprising behaviour for callers inspecting a document.
+pub type Map = LinkedHashMap<String, Value>;
+
+/// A number parsed out of a JSON5 document, keeping the integer/float
+/// distinction `Json5Deserializer` already makes rather than collapsing
+/// everything to `f64`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Number {
+    PosInt(u64),
+    NegInt(i64),
+    PosBigInt(u128),
+    NegBigInt(i128),
+    Float(f64),
+}
+
+/// Any valid JSON5 value.
+///
+/// This is the untyped, self-describing counterpart to deserializing into a
+/// concrete Rust type: `from_str::<Value>(input)` works on any well-formed
+/// JSON5 document, whatever shape it happens to be, except that integer
+/// literals too wide for 128 bits are rejected with an error.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(Number),
+    String(String),
+    Array(Vec<Value>),
+    Object(Map),
+}
+
+impl Value {
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            Value::Bool(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match *self {
+            Value::String(ref s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Value::Number(Number::Float(n)) => Some(n),
+            Value::Number(Number::PosInt(n)) => Some(n as f64),
+            Value::Number(Number::NegInt(n)) => Some(n as f64),
+            Value::Number(Number::PosBigInt(n)) => Some(n as f64),
+            Value::Number(Number::NegBigInt(n)) => Some(n as f64),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            Value::Number(Number::NegInt(n)) => Some(n),
+            Value::Number(Number::PosInt(n)) => i64::try_from(n).ok(),
+            Value::Number(Number::NegBigInt(n)) => i64::try_from(n).ok(),
+            Value::Number(Number::PosBigInt(n)) => i64::try_from(n).ok(),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> Index<&'a str> for Value {
+    type Output = Value;
+
+    fn index(&self, key: &str) -> &Value {
+        static NULL: Value = Value::Null;
+        match *self {
+            Value::Object(ref map) => map.get(key).unwrap_or(&NULL),
+            _ => &NULL,
+        }
+    }
+}
+
+impl Index<usize> for Value {
+    type Output = Value;
+
+    fn index(&self, index: usize) -> &Value {
+        static NULL: Value = Value::Null;
+        match *self {
+            Value::Array(ref vec) => vec.get(index).unwrap_or(&NULL),
+            _ => &NULL,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any valid JSON5 value")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Value, E> {
+        Ok(Value::Number(Number::NegInt(v)))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Value, E> {
+        Ok(Value::Number(Number::PosInt(v)))
+    }
+
+    fn visit_i128<E>(self, v: i128) -> Result<Value, E> {
+        Ok(Value::Number(Number::NegBigInt(v)))
+    }
+
+    fn visit_u128<E>(self, v: u128) -> Result<Value, E> {
+        Ok(Value::Number(Number::PosBigInt(v)))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Value, E> {
+        Ok(Value::Number(Number::Float(v)))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Value, E> {
+        Ok(Value::String(String::from(v)))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Value, E> {
+        Ok(Value::String(v))
+    }
+
+    fn visit_none<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Deserialize::deserialize(deserializer)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut vec = Vec::new();
+        while let Some(element) = seq.next_element()? {
+            vec.push(element);
+        }
+        Ok(Value::Array(vec))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut object = Map::new();
+        while let Some((key, value)) = map.next_entry()? {
+            object.insert(key, value);
+        }
+        Ok(Value::Object(object))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Number, Value};
+    use de::from_str;
+
+    #[test]
+    fn deserializes_an_arbitrary_document_into_a_value() {
+        let value: Value = from_str("{a: [1, 'two', true, null]}").unwrap();
+        assert_eq!(value["a"][0].as_i64(), Some(1));
+        assert_eq!(value["a"][1].as_str(), Some("two"));
+        assert_eq!(value["a"][2].as_bool(), Some(true));
+        assert_eq!(value["a"][3], Value::Null);
+    }
+
+    #[test]
+    fn out_of_range_integers_are_preserved_rather_than_erroring() {
+        let value: Value = from_str("0xffffffffffffffffff").unwrap();
+        assert_eq!(
+            value,
+            Value::Number(Number::PosBigInt(
+                u128::from_str_radix("ffffffffffffffffff", 16).unwrap()
+            ))
+        );
+    }
+}